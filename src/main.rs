@@ -1,19 +1,35 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 
 use clap::{App, Arg};
 use git2::Repository;
+use serde::Deserialize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Version {
     major: i32,
     minor: i32,
     patch: i32,
+    // Dot-separated prerelease identifiers, e.g. `rc.1` in `1.2.0-rc.1`.
+    pre: Option<String>,
+    // Dot-separated build metadata, e.g. `build.7`; ignored for ordering.
+    build: Option<String>,
 }
 
 impl Version {
+    // Tags are expected in `prefix-major.minor.patch[-pre][+build]` form
+    // (e.g. `dodgem-1.2.0-rc.1`) to match the `prefix-X.Y.Z` shape `tag_prefix`
+    // and the monorepo `--prefix` scan rely on; a bare `v1.2.0` with no
+    // hyphen between the prefix and the version isn't supported.
     fn parse_tag(name: &str) -> anyhow::Result<Version> {
-        let matcher = regex::Regex::new(r#".*-(\d+)\.(\d+)\.(\d+)"#).expect("Invalid regex");
-        let m = matcher.captures(name).unwrap();
+        let matcher = regex::Regex::new(
+            r#"-(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.-]+))?(?:\+([0-9A-Za-z.-]+))?$"#,
+        )
+        .expect("Invalid regex");
+        let m = matcher
+            .captures(name)
+            .ok_or_else(|| anyhow::anyhow!("Tag {} doesn't look like a version", name))?;
 
         fn match_to_int(m: &Option<regex::Match>) -> anyhow::Result<i32> {
             match m {
@@ -26,6 +42,8 @@ impl Version {
             major: match_to_int(&m.get(1))?,
             minor: match_to_int(&m.get(2))?,
             patch: match_to_int(&m.get(3))?,
+            pre: m.get(4).map(|m| m.as_str().to_string()),
+            build: m.get(5).map(|m| m.as_str().to_string()),
         })
     }
 
@@ -34,6 +52,8 @@ impl Version {
             major: self.major + 1,
             minor: 0,
             patch: 0,
+            pre: None,
+            build: None,
         }
     }
     fn bump_minor(&self) -> Version {
@@ -41,6 +61,8 @@ impl Version {
             major: self.major,
             minor: self.minor + 1,
             patch: 0,
+            pre: None,
+            build: None,
         }
     }
     fn bump_patch(&self) -> Version {
@@ -48,16 +70,784 @@ impl Version {
             major: self.major,
             minor: self.minor,
             patch: self.patch + 1,
+            pre: None,
+            build: None,
+        }
+    }
+
+    // Increment the `label.N` prerelease series, or start it at `label.0`.
+    fn bump_prerelease(&self, label: &str) -> Version {
+        let series_prefix = format!("{}.", label);
+        let next_pre = match &self.pre {
+            Some(pre) if pre.starts_with(&series_prefix) => {
+                let n: u32 = pre[series_prefix.len()..].parse().unwrap_or(0);
+                format!("{}{}", series_prefix, n + 1)
+            }
+            _ => format!("{}0", series_prefix),
+        };
+
+        Version {
+            major: self.major,
+            minor: self.minor,
+            patch: self.patch,
+            pre: Some(next_pre),
+            build: None,
         }
     }
 
     fn version_str(&self) -> String {
-        format!("{}.{}.{}", self.major, self.minor, self.patch)
+        let mut s = format!("{}.{}.{}", self.major, self.minor, self.patch);
+        if let Some(pre) = &self.pre {
+            s.push('-');
+            s.push_str(pre);
+        }
+        if let Some(build) = &self.build {
+            s.push('+');
+            s.push_str(build);
+        }
+        s
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    // major.minor.patch first, then prerelease (lower than no prerelease);
+    // build metadata is never considered.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_prerelease(a, b),
+            })
+    }
+}
+
+// Numeric identifiers compare numerically and rank below alphanumeric
+// ones; fewer identifiers ranks lower when the rest are equal.
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(xn), Ok(yn)) => xn.cmp(&yn),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod version_tests {
+    use super::*;
+
+    #[test]
+    fn prerelease_is_lower_precedence_than_release() {
+        let pre = Version::parse_tag("dodgem-1.2.0-rc.1").unwrap();
+        let release = Version::parse_tag("dodgem-1.2.0").unwrap();
+        assert!(pre < release);
+    }
+
+    #[test]
+    fn numeric_prerelease_identifiers_compare_numerically() {
+        assert_eq!(compare_prerelease("1.9", "1.10"), Ordering::Less);
+    }
+
+    #[test]
+    fn alphanumeric_prerelease_outranks_numeric() {
+        assert_eq!(compare_prerelease("rc.1", "1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn fewer_prerelease_identifiers_is_lower_precedence() {
+        assert_eq!(compare_prerelease("rc.1", "rc.1.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn parse_tag_round_trips_pre_and_build() {
+        let version = Version::parse_tag("dodgem-1.2.0-rc.1+build.7").unwrap();
+        assert_eq!(version.version_str(), "1.2.0-rc.1+build.7");
+    }
+
+    #[test]
+    fn parse_tag_rejects_a_non_version_tag() {
+        assert!(Version::parse_tag("stable").is_err());
+    }
+}
+
+// Ordered so `Ord`/`max` picks the highest-precedence bump across commits.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ConventionalBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+struct ConventionalCommit {
+    ctype: String,
+    scope: Option<String>,
+    breaking: bool,
+    description: String,
+}
+
+// `None` if the subject doesn't follow the `type(scope)!: description` shape.
+fn parse_conventional(summary: &str, body: Option<&str>) -> Option<ConventionalCommit> {
+    let matcher =
+        regex::Regex::new(r#"^(\w+)(?:\(([^)]*)\))?(!)?:\s*(.+)$"#).expect("Invalid regex");
+    let caps = matcher.captures(summary)?;
+
+    let breaking = caps.get(3).is_some()
+        || body
+            .map(|b| b.contains("BREAKING CHANGE:"))
+            .unwrap_or(false);
+
+    Some(ConventionalCommit {
+        ctype: caps.get(1)?.as_str().to_string(),
+        scope: caps.get(2).map(|m| m.as_str().to_string()),
+        breaking,
+        description: caps.get(4)?.as_str().to_string(),
+    })
+}
+
+fn classify_commit(summary: &str, body: Option<&str>) -> Option<ConventionalBump> {
+    let commit = parse_conventional(summary, body)?;
+    if commit.breaking {
+        return Some(ConventionalBump::Major);
+    }
+
+    match commit.ctype.as_str() {
+        "feat" => Some(ConventionalBump::Minor),
+        "fix" => Some(ConventionalBump::Patch),
+        _ => None,
+    }
+}
+
+// Howard Hinnant's civil_from_days: days since the Unix epoch -> (y, m, d).
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = if m <= 2 { y + 1 } else { y };
+    (y, m, d)
+}
+
+fn format_date(unix_seconds: i64) -> String {
+    let (y, m, d) = civil_from_days(unix_seconds.div_euclid(86400));
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// Upper-case the first letter of each `-`/`_`-separated word, e.g.
+// `parser` -> `Parser`, `api-docs` -> `Api-Docs`.
+fn title_case(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut capitalize_next = true;
+    for c in s.chars() {
+        if capitalize_next {
+            out.extend(c.to_uppercase());
+        } else {
+            out.push(c);
+        }
+        capitalize_next = c == '-' || c == '_';
+    }
+    out
+}
+
+#[cfg(test)]
+mod changelog_tests {
+    use super::*;
+
+    #[test]
+    fn parses_scope_and_breaking_bang() {
+        let commit = parse_conventional("feat(parser)!: support arrays", None).unwrap();
+        assert_eq!(commit.ctype, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("parser"));
+        assert!(commit.breaking);
+        assert_eq!(commit.description, "support arrays");
+    }
+
+    #[test]
+    fn breaking_change_footer_without_bang_is_still_breaking() {
+        let commit =
+            parse_conventional("fix: correct the thing", Some("BREAKING CHANGE: oops")).unwrap();
+        assert!(commit.breaking);
+    }
+
+    #[test]
+    fn non_conventional_subject_does_not_parse() {
+        assert!(parse_conventional("tweak some stuff", None).is_none());
+    }
+
+    #[test]
+    fn classify_commit_picks_highest_precedence() {
+        assert_eq!(
+            classify_commit("feat!: rewrite", None),
+            Some(ConventionalBump::Major)
+        );
+        assert_eq!(
+            classify_commit("feat: add thing", None),
+            Some(ConventionalBump::Minor)
+        );
+        assert_eq!(
+            classify_commit("fix: bug", None),
+            Some(ConventionalBump::Patch)
+        );
+        assert_eq!(classify_commit("chore: tidy", None), None);
+    }
+
+    #[test]
+    fn civil_from_days_matches_known_epoch_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19716), (2023, 12, 25));
+    }
+
+    #[test]
+    fn title_case_capitalizes_hyphenated_words() {
+        assert_eq!(title_case("parser"), "Parser");
+        assert_eq!(title_case("api-docs"), "Api-Docs");
+    }
+}
+
+fn render_changelog_section(
+    repo: &Repository,
+    commits: &[git2::Oid],
+    version: &str,
+    release_time: git2::Time,
+) -> anyhow::Result<String> {
+    let mut breaking = Vec::new();
+    let mut features = Vec::new();
+    let mut fixes = Vec::new();
+
+    for oid in commits {
+        let commit = repo.find_commit(*oid)?;
+        let parsed = match parse_conventional(commit.summary().unwrap_or(""), commit.body()) {
+            Some(p) => p,
+            None => continue,
+        };
+
+        let line = match &parsed.scope {
+            Some(scope) => format!("**{}:** {}", title_case(scope), parsed.description),
+            None => parsed.description.clone(),
+        };
+
+        if parsed.breaking {
+            breaking.push(line);
+        } else {
+            match parsed.ctype.as_str() {
+                "feat" => features.push(line),
+                "fix" => fixes.push(line),
+                _ => {}
+            }
+        }
+    }
+
+    let mut section = format!(
+        "## {} ({})\n",
+        version,
+        format_date(release_time.seconds())
+    );
+
+    for (title, lines) in [
+        ("Breaking Changes", &breaking),
+        ("Features", &features),
+        ("Bug Fixes", &fixes),
+    ] {
+        if lines.is_empty() {
+            continue;
+        }
+        section.push_str(&format!("\n### {}\n\n", title));
+        for line in lines {
+            section.push_str(&format!("- {}\n", line));
+        }
+    }
+
+    Ok(section)
+}
+
+// A file to rewrite on release: `key` is a dot-separated TOML path edited
+// structurally; `pattern` is a regex with one capture group bounding the
+// version text in an arbitrary file. Neither set falls back to the legacy
+// whole-file replacement.
+#[derive(Debug, Clone, Deserialize)]
+struct VersionTarget {
+    path: String,
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    pattern: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DodgemConfig {
+    #[serde(default, rename = "target")]
+    targets: Vec<VersionTarget>,
+    #[serde(default, rename = "package")]
+    packages: Vec<PackageConfig>,
+}
+
+// A package in a monorepo: tag prefix, directory, optional version targets.
+#[derive(Debug, Clone, Deserialize)]
+struct PackageConfig {
+    name: String,
+    prefix: String,
+    path: String,
+    #[serde(default)]
+    targets: Vec<VersionTarget>,
+}
+
+// How --package/--prefix was passed on the command line.
+enum PackageSelector {
+    Named(String),
+    Explicit { prefix: String, path: String },
+}
+
+// The resolved scope for a monorepo release.
+struct PackageScope {
+    prefix: String,
+    path: String,
+    targets: Vec<VersionTarget>,
+}
+
+// Compares against the first parent; a root commit is compared against the
+// empty tree, so it counts only if it adds files under `path`.
+fn commit_touches_path(repo: &Repository, oid: git2::Oid, path: &str) -> anyhow::Result<bool> {
+    let commit = repo.find_commit(oid)?;
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    let mut touches = false;
+    diff.foreach(
+        &mut |delta, _| {
+            let under_path = |p: Option<&Path>| p.map(|p| p.starts_with(path)).unwrap_or(false);
+            if under_path(delta.old_file().path()) || under_path(delta.new_file().path()) {
+                touches = true;
+            }
+            true
+        },
+        None,
+        None,
+        None,
+    )?;
+    Ok(touches)
+}
+
+// Breadth-first search back from `head` over the commit DAG, returning the
+// nearest tagged ancestor (if any) and every commit visited before it,
+// nearest first. A plain revwalk can reach a tag through a deep side of
+// history before a shallower one; BFS guarantees the first tag we hit is
+// the nearest one, and enqueuing every parent (not just the first) means
+// merge commits don't hide a tag down their other side.
+fn bfs_nearest_tag(
+    head: git2::Oid,
+    tagged: &HashSet<git2::Oid>,
+    mut parents_of: impl FnMut(git2::Oid) -> anyhow::Result<Vec<git2::Oid>>,
+) -> anyhow::Result<(Option<git2::Oid>, Vec<git2::Oid>)> {
+    let mut visited: HashSet<git2::Oid> = HashSet::new();
+    let mut queue: VecDeque<git2::Oid> = VecDeque::new();
+    let mut commits = Vec::new();
+
+    visited.insert(head);
+    queue.push_back(head);
+
+    while let Some(oid) = queue.pop_front() {
+        if tagged.contains(&oid) {
+            return Ok((Some(oid), commits));
+        }
+        commits.push(oid);
+        for parent_id in parents_of(oid)? {
+            if visited.insert(parent_id) {
+                queue.push_back(parent_id);
+            }
+        }
+    }
+    Ok((None, commits))
+}
+
+#[cfg(test)]
+mod bfs_nearest_tag_tests {
+    use super::*;
+
+    fn oid(byte: u8) -> git2::Oid {
+        git2::Oid::from_bytes(&[byte; 20]).unwrap()
+    }
+
+    #[test]
+    fn finds_the_shallower_tag_across_a_merge() {
+        // head -> [near (tagged), far -> farther (tagged)]
+        let head = oid(1);
+        let near = oid(2);
+        let far = oid(3);
+        let farther = oid(4);
+
+        let mut graph: HashMap<git2::Oid, Vec<git2::Oid>> = HashMap::new();
+        graph.insert(head, vec![near, far]);
+        graph.insert(near, vec![]);
+        graph.insert(far, vec![farther]);
+        graph.insert(farther, vec![]);
+
+        let tagged: HashSet<git2::Oid> = [near, farther].into_iter().collect();
+
+        let (tag, commits) =
+            bfs_nearest_tag(head, &tagged, |o| Ok(graph.get(&o).cloned().unwrap_or_default()))
+                .unwrap();
+
+        assert_eq!(tag, Some(near));
+        assert_eq!(commits, vec![head]);
+    }
+
+    #[test]
+    fn visits_each_commit_once_in_a_diamond() {
+        // head -> [a, b] -> base (tagged), reached via both branches
+        let head = oid(1);
+        let a = oid(2);
+        let b = oid(3);
+        let base = oid(4);
+
+        let mut graph: HashMap<git2::Oid, Vec<git2::Oid>> = HashMap::new();
+        graph.insert(head, vec![a, b]);
+        graph.insert(a, vec![base]);
+        graph.insert(b, vec![base]);
+        graph.insert(base, vec![]);
+
+        let tagged: HashSet<git2::Oid> = [base].into_iter().collect();
+
+        let (tag, commits) =
+            bfs_nearest_tag(head, &tagged, |o| Ok(graph.get(&o).cloned().unwrap_or_default()))
+                .unwrap();
+
+        assert_eq!(tag, Some(base));
+        assert_eq!(commits, vec![head, a, b]);
     }
+
+    #[test]
+    fn no_tagged_ancestor_returns_none() {
+        let head = oid(1);
+        let parent = oid(2);
+
+        let mut graph: HashMap<git2::Oid, Vec<git2::Oid>> = HashMap::new();
+        graph.insert(head, vec![parent]);
+        graph.insert(parent, vec![]);
+
+        let (tag, commits) = bfs_nearest_tag(head, &HashSet::new(), |o| {
+            Ok(graph.get(&o).cloned().unwrap_or_default())
+        })
+        .unwrap();
+
+        assert_eq!(tag, None);
+        assert_eq!(commits, vec![head, parent]);
+    }
+}
+
+// Map of commit ID to the highest-precedence version tag pointing at it,
+// scoped to `prefix_filter` when releasing a monorepo package.
+fn build_tagmap(
+    repo: &Repository,
+    prefix_filter: Option<&str>,
+) -> anyhow::Result<HashMap<git2::Oid, String>> {
+    let mut map: HashMap<git2::Oid, String> = HashMap::new();
+    repo.tag_foreach(|x, raw_tag_name| {
+        match repo.find_tag(x) {
+            Ok(tag_obj) => {
+                let target = tag_obj.target_id();
+                if let Ok(full_name) = String::from_utf8(raw_tag_name.into()) {
+                    // git_tag_foreach passes the full ref name
+                    // (`refs/tags/<name>`), not the short tag name.
+                    let name = full_name
+                        .strip_prefix("refs/tags/")
+                        .unwrap_or(&full_name)
+                        .to_string();
+                    let in_scope = match prefix_filter {
+                        Some(prefix) => tag_prefix(&name).map(|p| p == prefix).unwrap_or(false),
+                        None => true,
+                    };
+                    if !in_scope {
+                        return true;
+                    }
+
+                    // Several tags can point at the same commit; keep
+                    // whichever parses as the highest SemVer precedence.
+                    let keep_new = match map.get(&target) {
+                        Some(existing) => {
+                            match (Version::parse_tag(existing), Version::parse_tag(&name)) {
+                                (Ok(existing), Ok(new)) => new > existing,
+                                (Err(_), _) => true,
+                                (Ok(_), Err(_)) => false,
+                            }
+                        }
+                        None => true,
+                    };
+                    if keep_new {
+                        map.insert(target, name);
+                    }
+                }
+            }
+            Err(_) => {}
+        }
+        true
+    })?;
+    Ok(map)
+}
+
+#[cfg(test)]
+mod build_tagmap_tests {
+    use super::*;
+
+    // A unique scratch directory under the OS temp dir, removed on drop.
+    struct ScratchDir(std::path::PathBuf);
+
+    impl ScratchDir {
+        fn new(label: &str) -> ScratchDir {
+            let path = std::env::temp_dir().join(format!(
+                "dodgem-test-{}-{}-{:?}",
+                label,
+                std::process::id(),
+                std::thread::current().id()
+            ));
+            std::fs::create_dir_all(&path).unwrap();
+            ScratchDir(path)
+        }
+    }
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // A throwaway repo with one commit and an annotated tag on it.
+    fn repo_with_tag(label: &str, tag_name: &str) -> (ScratchDir, Repository) {
+        let dir = ScratchDir::new(label);
+        let repo = Repository::init(&dir.0).unwrap();
+
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let commit_id = repo
+            .commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[])
+            .unwrap();
+
+        let commit = repo.find_commit(commit_id).unwrap();
+        repo.tag(tag_name, commit.as_object(), &signature, tag_name, false)
+            .unwrap();
+
+        (dir, repo)
+    }
+
+    #[test]
+    fn stores_the_short_tag_name_not_the_full_ref() {
+        let (_dir, repo) = repo_with_tag("short-name", "dodgem-1.0.0");
+        let tagmap = build_tagmap(&repo, None).unwrap();
+        assert_eq!(
+            tagmap.values().next().map(String::as_str),
+            Some("dodgem-1.0.0")
+        );
+    }
+
+    #[test]
+    fn prefix_filter_matches_a_plain_tag_prefix() {
+        let (_dir, repo) = repo_with_tag("prefix-filter", "dodgem-1.0.0");
+        let tagmap = build_tagmap(&repo, Some("dodgem")).unwrap();
+        assert_eq!(tagmap.len(), 1);
+
+        let tagmap = build_tagmap(&repo, Some("other")).unwrap();
+        assert!(tagmap.is_empty());
+    }
+
+}
+
+// Load dodgem.toml from the repo root, if present.
+fn load_config(repo_path: &Path) -> anyhow::Result<Option<DodgemConfig>> {
+    let path = repo_path.join("dodgem.toml");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    Ok(Some(toml::from_str(&contents)?))
 }
 
-fn bumper(path: &str, bump_type: BumpType) -> anyhow::Result<()> {
+// A package's own targets when releasing a monorepo package, otherwise
+// dodgem.toml's targets, falling back to the historical package.py.
+fn resolve_targets(
+    repo_path: &Path,
+    package: Option<&PackageScope>,
+) -> anyhow::Result<Vec<VersionTarget>> {
+    if let Some(scope) = package {
+        if !scope.targets.is_empty() {
+            return Ok(scope.targets.clone());
+        }
+        if scope.path.is_empty() {
+            return Err(anyhow::anyhow!(
+                "--prefix needs a --package-path to know where package.py lives"
+            ));
+        }
+        return Ok(vec![VersionTarget {
+            path: format!("{}/package.py", scope.path),
+            key: None,
+            pattern: None,
+        }]);
+    }
+
+    match load_config(repo_path)? {
+        Some(config) if !config.targets.is_empty() => Ok(config.targets),
+        _ => Ok(vec![VersionTarget {
+            path: "package.py".to_string(),
+            key: None,
+            pattern: None,
+        }]),
+    }
+}
+
+// Rewrite a single TOML key in place, preserving unrelated keys/formatting.
+fn update_toml_target(file_path: &Path, key: &str, next_version: &str) -> anyhow::Result<()> {
+    let contents = std::fs::read_to_string(file_path)?;
+    let mut doc = contents
+        .parse::<toml_edit::Document>()
+        .map_err(|e| anyhow::anyhow!("{}: {}", file_path.display(), e))?;
+
+    let (last, parents) = key
+        .split('.')
+        .collect::<Vec<_>>()
+        .split_last()
+        .map(|(last, parents)| (last.to_string(), parents.to_vec()))
+        .ok_or_else(|| anyhow::anyhow!("Empty TOML key for {}", file_path.display()))?;
+
+    let mut item = doc.as_item_mut();
+    for part in &parents {
+        item = &mut item[*part];
+    }
+    item[last.as_str()] = toml_edit::value(next_version);
+
+    std::fs::write(file_path, doc.to_string())?;
+    Ok(())
+}
+
+// Replace just the text captured by `pattern`'s first group.
+fn update_pattern_target(file_path: &Path, pattern: &str, next_version: &str) -> anyhow::Result<()> {
+    let matcher = regex::Regex::new(pattern)?;
+    let contents = std::fs::read_to_string(file_path)?;
+    let group = matcher
+        .captures(&contents)
+        .and_then(|c| c.get(1))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Pattern `{}` didn't match a capture group in {}",
+                pattern,
+                file_path.display()
+            )
+        })?;
+
+    let mut updated = String::with_capacity(contents.len());
+    updated.push_str(&contents[..group.start()]);
+    updated.push_str(next_version);
+    updated.push_str(&contents[group.end()..]);
+
+    std::fs::write(file_path, updated)?;
+    Ok(())
+}
+
+fn apply_target(
+    repo_path: &Path,
+    target: &VersionTarget,
+    old_version: &str,
+    next_version: &str,
+) -> anyhow::Result<()> {
+    let file_path = repo_path.join(&target.path);
+    match (&target.key, &target.pattern) {
+        (Some(key), _) => update_toml_target(&file_path, key, next_version),
+        (None, Some(pattern)) => update_pattern_target(&file_path, pattern, next_version),
+        (None, None) => {
+            let contents = std::fs::read_to_string(&file_path)?;
+            let updated = contents.replace(old_version, next_version);
+            std::fs::write(&file_path, updated)?;
+            Ok(())
+        }
+    }
+}
+
+// Prepend `section` to CHANGELOG.md, creating it if it doesn't exist yet.
+fn prepend_changelog(repo_path: &std::path::Path, section: &str) -> anyhow::Result<()> {
+    let path = repo_path.join("CHANGELOG.md");
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+
+    let mut updated = section.to_string();
+    if !existing.is_empty() {
+        updated.push('\n');
+        updated.push_str(&existing);
+    }
+
+    std::fs::write(&path, updated)?;
+    Ok(())
+}
+
+// Split a tag name like `dodgem-1.2.0-rc.1` into its prefix (`dodgem`).
+fn tag_prefix(name: &str) -> anyhow::Result<String> {
+    let matcher = regex::Regex::new(
+        r#"-(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z.-]+))?(?:\+([0-9A-Za-z.-]+))?$"#,
+    )
+    .expect("Invalid regex");
+    let m = matcher
+        .find(name)
+        .ok_or_else(|| anyhow::anyhow!("Tag {} doesn't look like a version", name))?;
+    Ok(name[..m.start()].to_string())
+}
+
+// How to release the bump, as opposed to which bump (`BumpType`) it is.
+struct ReleaseOptions {
+    release: bool,
+    message_template: String,
+    dry_run: bool,
+    changelog: bool,
+    package: Option<PackageSelector>,
+    pre: Option<String>,
+}
+
+fn bumper(path: &str, bump_type: BumpType, opts: ReleaseOptions) -> anyhow::Result<()> {
+    let ReleaseOptions {
+        release,
+        message_template,
+        dry_run,
+        changelog,
+        package,
+        pre,
+    } = opts;
+
     let repo = Repository::discover(path)?;
+    let repo_path = repo.workdir().expect("Repo has no working directory");
 
     // Check HEAD points to branch `main`
     if repo.head()?.resolve()?.shorthand() != Some("main") {
@@ -75,61 +865,200 @@ fn bumper(path: &str, bump_type: BumpType) -> anyhow::Result<()> {
         ));
     }
 
-    // Get map of commit ID to tag-name
-    let tagmap = {
-        let mut map: HashMap<git2::Oid, String> = HashMap::new();
-        repo.tag_foreach(|x, raw_tag_name| {
-            match repo.find_tag(x) {
-                Ok(tag_obj) => {
-                    let target = tag_obj.target_id();
-                    if let Ok(name) = String::from_utf8(raw_tag_name.into()) {
-                        map.insert(target, name);
-                    }
-                }
-                Err(_) => {}
-            }
-            true
-        })?;
-        map
+    // Resolve which package (if any) we're releasing: which tag prefix is
+    // ours, and which directory's commits count towards this release.
+    let package_scope: Option<PackageScope> = match package {
+        Some(PackageSelector::Named(name)) => {
+            let config = load_config(repo_path)?.ok_or_else(|| {
+                anyhow::anyhow!("No dodgem.toml found to resolve --package {}", name)
+            })?;
+            let pkg = config
+                .packages
+                .into_iter()
+                .find(|p| p.name == name)
+                .ok_or_else(|| anyhow::anyhow!("No package `{}` configured in dodgem.toml", name))?;
+            Some(PackageScope {
+                prefix: pkg.prefix,
+                path: pkg.path,
+                targets: pkg.targets,
+            })
+        }
+        Some(PackageSelector::Explicit { prefix, path }) => Some(PackageScope {
+            prefix,
+            path,
+            targets: Vec::new(),
+        }),
+        None => None,
     };
 
-    // Walk commits, newest to oldest
-    let mut walker = repo.revwalk()?;
-    walker.push_head()?;
-    walker.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    // Map of commit ID to tag-name, scoped to this package's prefix when
+    // releasing a monorepo package.
+    let tagmap = build_tagmap(&repo, package_scope.as_ref().map(|s| s.prefix.as_str()))?;
 
-    // Find last tagged commit from current branch
-    let prev_tag = walker
-        .filter_map(Result::ok)
-        .filter(|o| tagmap.contains_key(&o))
-        .next();
+    // Walk back from HEAD over the commit DAG to find the nearest tagged
+    // ancestor, noting every commit in between so an `auto` bump can
+    // inspect them. When scoped to a package, commits that don't touch its
+    // directory don't count.
+    let head_oid = repo.head()?.peel_to_commit()?.id();
+    let tagged: HashSet<git2::Oid> = tagmap.keys().copied().collect();
+    let (prev_tag, commits_before_scope) = bfs_nearest_tag(head_oid, &tagged, |oid| {
+        Ok(repo.find_commit(oid)?.parent_ids().collect())
+    })?;
+
+    let mut commits_since_tag: Vec<git2::Oid> = Vec::new();
+    for oid in commits_before_scope {
+        let in_scope = match &package_scope {
+            Some(scope) => commit_touches_path(&repo, oid, &scope.path)?,
+            None => true,
+        };
+        if in_scope {
+            commits_since_tag.push(oid);
+        }
+    }
 
     // Generate new version string
-    let (old_version, next_version) = match prev_tag {
+    let (old_version, next_version, prefix) = match prev_tag {
         Some(t) => {
-            let version = Version::parse_tag(&tagmap[&t]).unwrap();
-            let next = match bump_type {
-                BumpType::major => version.bump_major(),
-                BumpType::minor => version.bump_minor(),
-                BumpType::patch => version.bump_patch(),
+            let tag_name = &tagmap[&t];
+            let version = Version::parse_tag(tag_name)?;
+            let prefix = tag_prefix(tag_name)?;
+
+            let apply_bump_type = |version: &Version| -> anyhow::Result<Version> {
+                match bump_type {
+                    BumpType::major => Ok(version.bump_major()),
+                    BumpType::minor => Ok(version.bump_minor()),
+                    BumpType::patch => Ok(version.bump_patch()),
+                    BumpType::auto => {
+                        let bump = commits_since_tag
+                            .iter()
+                            .filter_map(|oid| {
+                                let commit = repo.find_commit(*oid).ok()?;
+                                classify_commit(commit.summary().unwrap_or(""), commit.body())
+                            })
+                            .max();
+                        match bump {
+                            Some(ConventionalBump::Major) => Ok(version.bump_major()),
+                            Some(ConventionalBump::Minor) => Ok(version.bump_minor()),
+                            Some(ConventionalBump::Patch) => Ok(version.bump_patch()),
+                            None => Err(anyhow::anyhow!(
+                                "No conventional commits since {} imply a version bump",
+                                &tagmap[&t]
+                            )),
+                        }
+                    }
+                }
+            };
+
+            // Continuing the same `-label.N` series just increments `N` in
+            // place; anything else (a final release, or a different
+            // series) needs the selected bump applied first so the
+            // prerelease previews the *next* release, not a redecorated
+            // copy of the one already tagged.
+            let next = match &pre {
+                Some(label) => {
+                    let continuing_series = version
+                        .pre
+                        .as_deref()
+                        .map(|p| p.starts_with(&format!("{}.", label)))
+                        .unwrap_or(false);
+                    if continuing_series {
+                        version.bump_prerelease(label)
+                    } else {
+                        apply_bump_type(&version)?.bump_prerelease(label)
+                    }
+                }
+                None => apply_bump_type(&version)?,
             };
-            (version.version_str(), next.version_str())
+            (version.version_str(), next.version_str(), prefix)
         }
         None => return Err(anyhow::anyhow!("No previous tag found")),
     };
 
+    let release_message = message_template.replace("{version}", &next_version);
+    let tag_name = format!("{}-{}", prefix, next_version);
+
+    if release && repo.find_reference(&format!("refs/tags/{}", tag_name)).is_ok() {
+        return Err(anyhow::anyhow!("Tag {} already exists", tag_name));
+    }
+
     // Update files in repo
-    let repo_path = repo.workdir().expect("Repo has no working directory");
-    let f = repo_path.join("package.py");
-    eprintln!(
-        "Updating {} from {} to {}",
-        &f.to_str().unwrap_or("???"),
-        &old_version,
-        &next_version
-    );
-    let contents = std::fs::read_to_string(&f)?;
-    let updated = contents.replace(&old_version, &next_version);
-    std::fs::write(&f, &updated)?;
+    let changelog_path = repo_path.join("CHANGELOG.md");
+    let targets = resolve_targets(repo_path, package_scope.as_ref())?;
+
+    if dry_run {
+        for target in &targets {
+            eprintln!(
+                "Would update {} from {} to {}",
+                repo_path.join(&target.path).to_str().unwrap_or("???"),
+                &old_version,
+                &next_version
+            );
+        }
+        if changelog {
+            eprintln!(
+                "Would prepend a {} section to {}",
+                &next_version,
+                changelog_path.to_str().unwrap_or("???")
+            );
+        }
+        if release {
+            eprintln!(
+                "Would commit \"{}\" and tag {}",
+                &release_message, &tag_name
+            );
+        }
+        return Ok(());
+    }
+
+    for target in &targets {
+        eprintln!(
+            "Updating {} from {} to {}",
+            repo_path.join(&target.path).to_str().unwrap_or("???"),
+            &old_version,
+            &next_version
+        );
+        apply_target(repo_path, target, &old_version, &next_version)?;
+    }
+
+    if changelog {
+        // Dated from the release signature's time when we're also tagging,
+        // otherwise from the most recent commit being released.
+        let release_time = if release {
+            repo.signature()?.when()
+        } else {
+            repo.head()?.peel_to_commit()?.time()
+        };
+        let section =
+            render_changelog_section(&repo, &commits_since_tag, &next_version, release_time)?;
+        prepend_changelog(repo_path, &section)?;
+    }
+
+    if release {
+        let mut index = repo.index()?;
+        for target in &targets {
+            index.add_path(Path::new(&target.path))?;
+        }
+        if changelog {
+            index.add_path(changelog_path.strip_prefix(repo_path)?)?;
+        }
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+
+        let sig = repo.signature()?;
+        let parent = repo.head()?.peel_to_commit()?;
+        let commit_oid = repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            &release_message,
+            &tree,
+            &[&parent],
+        )?;
+        let commit_obj = repo.find_object(commit_oid, Some(git2::ObjectType::Commit))?;
+        repo.tag(&tag_name, &commit_obj, &sig, &release_message, false)?;
+
+        eprintln!("Created commit {} and tag {}", commit_oid, &tag_name);
+    }
 
     Ok(())
 }
@@ -143,6 +1072,7 @@ arg_enum! {
         major,
         minor,
         patch,
+        auto,
     }
 }
 
@@ -160,12 +1090,82 @@ fn main() -> anyhow::Result<()> {
                 .short("p")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("release")
+                .long("release")
+                .help("Commit the version bump and create an annotated release tag"),
+        )
+        .arg(
+            Arg::with_name("message")
+                .long("message")
+                .takes_value(true)
+                .default_value("chore(release): {version}")
+                .help("Commit/tag message template; {version} is replaced with the new version"),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .help("Print the planned changes without writing anything"),
+        )
+        .arg(
+            Arg::with_name("changelog")
+                .long("changelog")
+                .help("Prepend a release section to CHANGELOG.md"),
+        )
+        .arg(
+            Arg::with_name("package")
+                .long("package")
+                .takes_value(true)
+                .conflicts_with("prefix")
+                .help("Release a single monorepo package, as configured in dodgem.toml"),
+        )
+        .arg(
+            Arg::with_name("prefix")
+                .long("prefix")
+                .takes_value(true)
+                .requires("package-path")
+                .help("Release a monorepo package by tag prefix, without needing dodgem.toml"),
+        )
+        .arg(
+            Arg::with_name("package-path")
+                .long("package-path")
+                .takes_value(true)
+                .requires("prefix")
+                .help("Directory whose commits count towards the --prefix package"),
+        )
+        .arg(
+            Arg::with_name("pre")
+                .long("pre")
+                .takes_value(true)
+                .value_name("label")
+                .help("Release a prerelease of the bumped version, or continue an existing label.N series, e.g. --pre rc for 1.2.0-rc.N"),
+        )
         .get_matches();
 
     let bump_type = value_t_or_exit!(args.value_of("type"), BumpType);
     let path = args.value_of("path").unwrap_or(".");
+    let package = if let Some(name) = args.value_of("package") {
+        Some(PackageSelector::Named(name.to_string()))
+    } else {
+        args.value_of("prefix").map(|prefix| PackageSelector::Explicit {
+            prefix: prefix.to_string(),
+            path: args.value_of("package-path").unwrap_or("").to_string(),
+        })
+    };
+
+    let opts = ReleaseOptions {
+        release: args.is_present("release"),
+        message_template: args
+            .value_of("message")
+            .unwrap_or("chore(release): {version}")
+            .to_string(),
+        dry_run: args.is_present("dry-run"),
+        changelog: args.is_present("changelog"),
+        package,
+        pre: args.value_of("pre").map(|label| label.to_string()),
+    };
 
-    bumper(path, bump_type)?;
+    bumper(path, bump_type, opts)?;
 
     Ok(())
 }